@@ -1,6 +1,14 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-type Scope = HashMap<Var, u32>;
+/// Monotonically increasing counter used to mint variable names that are
+/// guaranteed fresh (never produced before), for alpha-renaming a bound
+/// variable that would otherwise capture a substituted term's free variable.
+static FRESH_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn fresh_ident() -> u32 {
+    FRESH_COUNTER.fetch_add(1, Ordering::Relaxed)
+}
 
 /// A variable used in a lambda expression.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -32,6 +40,10 @@ impl Var {
     }
 }
 
+/// Identifies a native primitive registered in a `crate::primitive::Registry`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PrimId(pub(crate) u32);
+
 /// An untyped lambda expression.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expr {
@@ -41,6 +53,32 @@ pub enum Expr {
     Abstraction(Var, Box<Expr>),
     /// An expression of the form `(M N)`.
     Application(Box<Expr>, Box<Expr>),
+    /// An opaque reference to a native function registered in a `Registry`,
+    /// which expands it in place once it's been applied to enough
+    /// arguments. Outside of a `Registry`'s evaluation, it behaves like an
+    /// ordinary irreducible atom.
+    Primitive(PrimId),
+}
+
+/// A beta-reduction order `eval_with` can be asked to use. Where
+/// `NormalOrder` and `ApplicativeOrder` both terminate they reach the same
+/// normal form (up to renaming of bound variables), but they differ on which
+/// terms they normalize; `CallByName` stops earlier, at weak head normal form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Leftmost-outermost: reduce the head of an application before its
+    /// argument, and keep reducing until no redexes remain anywhere,
+    /// including under binders and inside neutral applications. Finds a
+    /// normal form whenever one exists.
+    NormalOrder,
+    /// Call-by-value: reduce both sides of an application to normal form
+    /// *before* substituting. Can diverge on inputs whose discarded argument
+    /// doesn't itself have a normal form, even if the overall term does.
+    ApplicativeOrder,
+    /// Like `NormalOrder` but stops at weak head normal form: once the head
+    /// of the expression is not a reducible application, leaves everything
+    /// else — abstraction bodies and neutral arguments alike — untouched.
+    CallByName,
 }
 
 impl Expr {
@@ -66,78 +104,218 @@ impl Expr {
             Variable(v) => v.code(),
             Abstraction(v, e) => format!("(\\{}. {})", v.code(), &*e.code()),
             Application(e1, e2) => format!("({} {})", &*e1.code(), &*e2.code()),
+            Primitive(id) => format!("<prim#{}>", id.0),
         }
     }
 
-    /// Reduce a lambda expression.
-    pub fn eval(&self) -> Expr {
-        self.canonicalize().eval_inner()
+    /// Reduce a lambda expression using leftmost-outermost (normal-order)
+    /// reduction. Shorthand for `eval_with(Strategy::NormalOrder)`.
+    pub fn eval(&self) -> Result<Expr, EvalError> {
+        self.eval_with(Strategy::NormalOrder)
+    }
+
+    /// Reduce a lambda expression under the given reduction `Strategy`. See
+    /// `Strategy` for how the three orders differ. Non-terminating terms are
+    /// cut off after `MAX_STEPS` beta reductions and reported as
+    /// `EvalError::StepLimitExceeded`, carrying the best partial reduct found
+    /// so far, instead of looping forever.
+    pub fn eval_with(&self, strategy: Strategy) -> Result<Expr, EvalError> {
+        let mut steps = 0;
+        let reduced = match strategy {
+            Strategy::NormalOrder => self.eval_normal(&mut steps),
+            Strategy::ApplicativeOrder => self.eval_applicative(&mut steps),
+            Strategy::CallByName => self.whnf(&mut steps),
+        };
+        if steps >= MAX_STEPS {
+            Err(EvalError::StepLimitExceeded { steps, partial: reduced })
+        } else {
+            Ok(reduced)
+        }
     }
 }
 
+/// Maximum number of beta reductions `eval_with` will perform before giving
+/// up and returning the best partial reduct, so a non-normalizing term like
+/// `(\x. x x)(\x. x x)` fails gracefully instead of looping forever.
+pub(crate) const MAX_STEPS: u32 = 100_000;
+
+/// Errors that can occur while reducing an `Expr`, in the spirit of rhai's
+/// `EvalAltResult`: a typed enum that carries whatever data the caller needs
+/// to react, rather than a bare panic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// Reduction did not settle on a normal form within `steps` beta
+    /// reductions; `partial` is the best reduct found before giving up.
+    StepLimitExceeded { steps: u32, partial: Expr },
+}
+
 impl Expr {
-    fn sub(&self, var: &Var, e: Expr) -> Expr {
+    /// Names occurring free (not bound by an enclosing abstraction) in this
+    /// expression.
+    pub fn free_vars(&self) -> HashSet<String> {
         use Expr::*;
         match self {
-            ev @ Variable(v) => {
-                match (var.ident, v.ident) {
-                    // (Some(i), Some(j)) if i == j && var.name == v.name => e.clone(),
-                    (Some(i), Some(j)) if i == j => e.clone(),
-                    _ => ev.clone(),
-                }
+            Variable(v) => HashSet::from([v.code()]),
+            Abstraction(v, body) => {
+                let mut fv = body.free_vars();
+                fv.remove(&v.code());
+                fv
+            }
+            Application(e1, e2) => {
+                let mut fv = e1.free_vars();
+                fv.extend(e2.free_vars());
+                fv
             }
-            Abstraction(v, f) => Abstraction(v.clone(), Box::new(f.sub(var, e.clone()))),
-            Application(e1, e2) => Application(
-                Box::new(e1.sub(var, e.clone())),
-                Box::new(e2.sub(var, e.clone())),
-            ),
+            Primitive(_) => HashSet::new(),
         }
     }
 
-    fn canonicalize_inner(&self, scope: &Scope, d: u32) -> Expr {
+    /// Rename every free occurrence of `from` to `to`. Used only to
+    /// alpha-convert a bound variable immediately before it would otherwise
+    /// capture a substituted term's free variable.
+    fn rename(&self, from: &Var, to: &Var) -> Expr {
         use Expr::*;
         match self {
-            Abstraction(v, e) => {
-                // Enter a deeper scope
-                let mut new_scope = scope.clone();
-                new_scope.insert(v.clone(), d + 1);
+            Variable(v) if v == from => Variable(to.clone()),
+            Variable(_) => self.clone(),
+            Abstraction(v, _) if v == from => self.clone(),
+            Abstraction(v, body) => Abstraction(v.clone(), Box::new(body.rename(from, to))),
+            Application(e1, e2) => {
+                Application(Box::new(e1.rename(from, to)), Box::new(e2.rename(from, to)))
+            }
+            Primitive(_) => self.clone(),
+        }
+    }
 
-                let ec = e.canonicalize_inner(&new_scope, d + 1);
-                Abstraction(v.with_ident(d + 1), Box::new(ec))
+    /// Capture-avoiding substitution: replace every free occurrence of `x`
+    /// in `self` with `n`. For an abstraction `\y. p`, if `y` would capture
+    /// a free variable of `n`, `y` is alpha-renamed to a fresh name before
+    /// descending into `p`. Visible to `crate::primitive`, which drives its
+    /// own copy of the reduction loop to splice in native primitives.
+    pub(crate) fn subst(&self, x: &Var, n: &Expr) -> Expr {
+        use Expr::*;
+        match self {
+            Variable(y) => {
+                if y == x {
+                    n.clone()
+                } else {
+                    self.clone()
+                }
             }
-            Application(e1, e2) => {
-                let e1c = e1.canonicalize_inner(scope, d);
-                let e2c = e2.canonicalize_inner(scope, d);
-                Application(Box::new(e1c), Box::new(e2c))
+            Application(p, q) => Application(Box::new(p.subst(x, n)), Box::new(q.subst(x, n))),
+            Abstraction(y, p) => {
+                if y == x {
+                    // x is shadowed by y; nothing free to substitute.
+                    self.clone()
+                } else if n.free_vars().contains(&y.code()) {
+                    let fresh = y.with_ident(fresh_ident());
+                    let renamed_body = p.rename(y, &fresh);
+                    Abstraction(fresh, Box::new(renamed_body.subst(x, n)))
+                } else {
+                    Abstraction(y.clone(), Box::new(p.subst(x, n)))
+                }
+            }
+            Primitive(_) => self.clone(),
+        }
+    }
+
+    /// Reduce the leftmost-outermost redex to weak head normal form: if the
+    /// head of an application is itself an abstraction once reduced,
+    /// substitute into its body *without* first reducing the argument, and
+    /// keep going. Stops as soon as the head is not an abstraction, leaving
+    /// subterms (including the argument) unreduced. Gives up once `steps`
+    /// reaches `MAX_STEPS`, leaving the redex unreduced instead of looping
+    /// forever on a non-terminating term. Loops rather than recursing on
+    /// each successive redex, so a long-running reduction (like `MAX_STEPS`
+    /// iterations of a self-application) doesn't also blow the call stack.
+    fn whnf(&self, steps: &mut u32) -> Expr {
+        use Expr::*;
+        let mut current = self.clone();
+        loop {
+            match current {
+                Application(e1, e2) => match e1.whnf(steps) {
+                    Abstraction(var, body) => {
+                        if *steps >= MAX_STEPS {
+                            return Application(Box::new(Abstraction(var, body)), e2);
+                        }
+                        *steps += 1;
+                        current = body.subst(&var, &e2);
+                    }
+                    other => return Application(Box::new(other), e2),
+                },
+                other => return other,
             }
-            Variable(var) => {
-                let lookup = scope
-                    .get(&var)
-                    .map(|t| var.with_ident(*t))
-                    .unwrap_or_else(|| var.clone());
-                Variable(lookup)
+        }
+    }
+
+    /// Normal-order reduction to full normal form: after `whnf` settles on a
+    /// non-reducible head, recurse into the abstraction body or into both
+    /// sides of a neutral application so residual redexes there are
+    /// normalized too.
+    fn eval_normal(&self, steps: &mut u32) -> Expr {
+        use Expr::*;
+        match self.whnf(steps) {
+            Abstraction(var, body) => Abstraction(var, Box::new(body.eval_normal(steps))),
+            Application(e1, e2) => {
+                Application(Box::new(e1.eval_normal(steps)), Box::new(e2.eval_normal(steps)))
             }
+            other => other,
         }
     }
 
-    /// Canonicalize bound variables to avoid binding issues.
-    fn canonicalize(&self) -> Expr {
-        self.canonicalize_inner(&HashMap::new(), 0)
+    /// Applicative-order (call-by-value) reduction: reduce both sides of an
+    /// application to normal form *before* substituting, unlike `whnf`/
+    /// `eval_normal` which substitute the argument unevaluated. Shares the
+    /// same `MAX_STEPS` budget as `whnf`, and likewise loops across
+    /// successive redexes instead of recursing so it doesn't blow the call
+    /// stack on a long-running reduction.
+    fn eval_applicative(&self, steps: &mut u32) -> Expr {
+        use Expr::*;
+        let mut current = self.clone();
+        loop {
+            match current {
+                Application(e1, e2) => {
+                    let e1a = e1.eval_applicative(steps);
+                    let e2a = e2.eval_applicative(steps);
+                    match e1a {
+                        Abstraction(var, body) => {
+                            if *steps >= MAX_STEPS {
+                                return Application(Box::new(Abstraction(var, body)), Box::new(e2a));
+                            }
+                            *steps += 1;
+                            current = body.subst(&var, &e2a);
+                        }
+                        other => return Application(Box::new(other), Box::new(e2a)),
+                    }
+                }
+                Abstraction(var, body) => return Abstraction(var, Box::new(body.eval_applicative(steps))),
+                other => return other,
+            }
+        }
     }
 
-    /// Evaluate an expression by performing beta-reduction and alpha-renaming
-    /// when necessary.
-    fn eval_inner(&self) -> Expr {
+    /// Perform a single leftmost-outermost beta-reduction step, or return
+    /// `None` if none applies. Used by the REPL's `:step` command to show
+    /// reduction one redex at a time instead of jumping straight to `eval`'s
+    /// result.
+    pub fn step(&self) -> Option<Expr> {
         use Expr::*;
-        match self.clone() {
+        match self {
             Application(e1, e2) => {
-                let (e1, e2) = (Box::new(e1.eval_inner()), Box::new(e2.eval_inner()));
-                match *e1.clone() {
-                    Abstraction(var, e) => e.sub(&var, *e2.clone()).eval_inner(),
-                    _ => Application(e1, e2),
+                if let Abstraction(var, body) = &**e1 {
+                    return Some(body.subst(var, e2));
                 }
+                if let Some(e1s) = e1.step() {
+                    return Some(Application(Box::new(e1s), e2.clone()));
+                }
+                if let Some(e2s) = e2.step() {
+                    return Some(Application(e1.clone(), Box::new(e2s)));
+                }
+                None
             }
-            other => other,
+            Abstraction(var, body) => body.step().map(|b| Abstraction(var.clone(), Box::new(b))),
+            Variable(_) => None,
+            Primitive(_) => None,
         }
     }
 }
@@ -148,10 +326,17 @@ pub mod tests {
 
     /// Helper for testing.
     pub fn eval(e: &Expr) -> Expr {
-        let reduced = e.eval();
-        println!("{} --> {}", e.code(), reduced.code());
-        println!("reduced: {:#?}", reduced);
-        reduced
+        match e.eval() {
+            Ok(reduced) => {
+                println!("{} --> {}", e.code(), reduced.code());
+                println!("reduced: {:#?}", reduced);
+                reduced
+            }
+            Err(EvalError::StepLimitExceeded { steps, partial }) => {
+                println!("{} --> (step limit of {steps} exceeded) {}", e.code(), partial.code());
+                partial
+            }
+        }
     }
 
     #[test]
@@ -182,28 +367,6 @@ pub mod tests {
         println!("{}", apply_true.code());
     }
 
-    #[test]
-    fn test_canonicalize() {
-        // (\x.x) y
-        let id_fn = Expr::abstraction("x", Expr::variable("x"));
-        let apply_y = Expr::application(id_fn.clone(), Expr::variable("y"));
-
-        let c = apply_y.canonicalize();
-        println!("{}", c.code());
-
-        // (\x. (\x. x) x) x
-        let x = Expr::variable("x");
-
-        let f = Expr::application(
-            Expr::abstraction("x", Expr::application(id_fn, x.clone())),
-            x,
-        );
-        println!("{}", f.code());
-
-        let c = f.canonicalize();
-        println!("{}", c.code());
-    }
-
     #[test]
     fn test_eval1() {
         // (\x.x) y
@@ -290,4 +453,103 @@ pub mod tests {
         let output = Expr::application(final_application, Expr::variable("t"));
         eval(&output);
     }
+
+    /// `(\x.\y.x) y` naively substitutes the outer `y` for `x` inside `\y.x`
+    /// and would wrongly capture it as the bound `y`, reducing to `\y.y`
+    /// (the identity function) instead of the correct `\yN.y`, where `yN` is
+    /// the bound `y` renamed with a fresh numeric suffix.
+    #[test]
+    fn test_capture_avoidance() {
+        let inner = Expr::abstraction("y", Expr::variable("x"));
+        let outer = Expr::abstraction("x", inner);
+        let applied = Expr::application(outer, Expr::variable("y"));
+
+        match eval(&applied) {
+            Expr::Abstraction(bound, body) => {
+                assert_ne!(bound.code(), "y", "bound variable must be renamed to avoid capture");
+                assert_eq!(*body, Expr::variable("y"));
+            }
+            other => panic!("expected an abstraction, got {}", other.code()),
+        }
+    }
+
+    /// `(\x.\y. x) a ((\x. x x)(\x. x x))` discards its second argument, a
+    /// term with no normal form. Normal order never forces an argument it
+    /// doesn't substitute anywhere, so it terminates where applicative
+    /// (call-by-value) order would diverge.
+    #[test]
+    fn test_normal_order_discards_nonterminating_argument() {
+        let omega_body = Expr::abstraction("x", Expr::application(Expr::variable("x"), Expr::variable("x")));
+        let omega = Expr::application(omega_body.clone(), omega_body);
+
+        let k = Expr::abstraction("x", Expr::abstraction("y", Expr::variable("x")));
+        let term = Expr::application(Expr::application(k, Expr::variable("a")), omega);
+
+        assert_eq!(term.eval_with(Strategy::NormalOrder).unwrap(), Expr::variable("a"));
+    }
+
+    /// `CallByName` stops at weak head normal form, leaving the abstraction
+    /// body and neutral arguments untouched; `NormalOrder` keeps going to
+    /// reach a full normal form.
+    #[test]
+    fn test_call_by_name_stops_at_whnf() {
+        let redex = Expr::application(
+            Expr::abstraction("x", Expr::variable("x")),
+            Expr::variable("a"),
+        );
+        let term = Expr::abstraction("y", redex.clone());
+
+        // Under the binder, the inner redex is left unreduced.
+        assert_eq!(term.eval_with(Strategy::CallByName).unwrap(), term);
+        // Normal order reduces underneath the binder too.
+        assert_eq!(
+            term.eval_with(Strategy::NormalOrder).unwrap(),
+            Expr::abstraction("y", Expr::variable("a"))
+        );
+    }
+
+    /// `(\x. x x)(\x. x x)` never reaches a normal form under any reduction
+    /// order; the step cap must return the best partial reduct instead of
+    /// recursing forever.
+    #[test]
+    fn test_step_limit_terminates() {
+        let omega_body = Expr::abstraction("x", Expr::application(Expr::variable("x"), Expr::variable("x")));
+        let omega = Expr::application(omega_body.clone(), omega_body);
+        eval(&omega);
+    }
+
+    /// `eval` surfaces a non-terminating term as a typed `EvalError`, not a
+    /// panic or a silently-truncated result.
+    #[test]
+    fn test_step_limit_exceeded_is_an_error() {
+        let omega_body = Expr::abstraction("x", Expr::application(Expr::variable("x"), Expr::variable("x")));
+        let omega = Expr::application(omega_body.clone(), omega_body);
+
+        match omega.eval() {
+            Err(EvalError::StepLimitExceeded { steps, .. }) => assert_eq!(steps, MAX_STEPS),
+            Ok(reduced) => panic!("expected a step-limit error, got {}", reduced.code()),
+        }
+    }
+
+    /// A normalizing term still reduces to `Ok`, unaffected by the fallible
+    /// `eval` signature.
+    #[test]
+    fn test_eval_ok_on_normal_form() {
+        let id_fn = Expr::abstraction("x", Expr::variable("x"));
+        let apply_y = Expr::application(id_fn, Expr::variable("y"));
+        assert_eq!(apply_y.eval(), Ok(Expr::variable("y")));
+    }
+
+    /// On a terminating term, applicative order agrees with normal order.
+    #[test]
+    fn test_applicative_order_agrees_on_terminating_term() {
+        let id_fn = Expr::abstraction("x", Expr::variable("x"));
+        let inner = Expr::application(id_fn.clone(), Expr::variable("z"));
+        let term = Expr::application(id_fn, inner);
+
+        assert_eq!(
+            term.eval_with(Strategy::ApplicativeOrder).unwrap(),
+            term.eval_with(Strategy::NormalOrder).unwrap()
+        );
+    }
 }
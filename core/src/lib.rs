@@ -0,0 +1,4 @@
+pub mod ast;
+pub mod parser;
+pub mod primitive;
+pub mod types;
@@ -0,0 +1,297 @@
+//! A runtime counterpart to the `lambda!` macro's `astize`: parses a lambda
+//! term out of a plain string instead of a `TokenStream`, sharing the same
+//! grammar: `λ`, `\`, and `L` are binders only in binder position (their own
+//! token, followed by one or more variables and a `.`), application is
+//! left-associative and binds tighter than abstraction, and an abstraction
+//! body extends as far right as the enclosing group allows. Round-trips with
+//! `Expr::code`'s pretty-printer.
+
+use std::fmt;
+
+use crate::ast::Expr;
+
+const LAMBDA_TOK: &str = "L";
+
+/// A parse failure with the byte offset into the input where it was
+/// detected, so callers can point at the offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pos: usize,
+    message: String,
+}
+
+impl ParseError {
+    fn new(pos: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            pos,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.pos)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Dot,
+    LParen,
+    RParen,
+}
+
+/// A token paired with the byte offset it started at (for error reporting)
+/// and the byte length of the source text it was scanned from (which, for
+/// the single-character `\`/`λ` binder token, may differ from its `Ident`
+/// text `"L"` — `λ` is multiple bytes in UTF-8).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Spanned {
+    token: Token,
+    pos: usize,
+    len: usize,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(pos, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Spanned { token: Token::LParen, pos, len: c.len_utf8() });
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Spanned { token: Token::RParen, pos, len: c.len_utf8() });
+            }
+            '.' => {
+                chars.next();
+                tokens.push(Spanned { token: Token::Dot, pos, len: c.len_utf8() });
+            }
+            '\\' | 'λ' => {
+                chars.next();
+                tokens.push(Spanned { token: Token::Ident(LAMBDA_TOK.to_string()), pos, len: c.len_utf8() });
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&(_, c)) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let len = ident.len();
+                tokens.push(Spanned { token: Token::Ident(ident), pos, len });
+            }
+            other => return Err(ParseError::new(pos, format!("unexpected character '{other}'"))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Find the end of the parenthesized group starting right after an
+/// already-consumed `(`, returning the inner tokens and the remainder.
+fn split_group(tokens: &[Spanned], open_pos: usize) -> Result<(&[Spanned], &[Spanned]), ParseError> {
+    let mut depth = 1;
+    for (i, tok) in tokens.iter().enumerate() {
+        match tok.token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((&tokens[..i], &tokens[i + 1..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(ParseError::new(open_pos, "unmatched '('"))
+}
+
+/// Is `ident` a binder keyword? Recognized only as a whole token, never as a
+/// prefix, so an ordinary variable named `List` or `Left` is just a
+/// variable.
+fn is_binder(ident: &str) -> bool {
+    ident == LAMBDA_TOK
+}
+
+fn parse_tokens(tokens: &[Spanned]) -> Result<Expr, ParseError> {
+    let mut ast: Vec<Expr> = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::Ident(ident) if is_binder(ident) => {
+                // One or more binder variables precede the `.`; `L x y. e`
+                // desugars to nested abstractions `L x. L y. e`. If none
+                // follow, this wasn't a binder after all — treat the lone
+                // `L`/`λ` token as an ordinary variable instead of erroring,
+                // so `f L` (applying `f` to a variable named `L`) parses.
+                let mut vars = vec![];
+                let mut j = i + 1;
+                while let Some(Token::Ident(v)) = tokens.get(j).map(|t| &t.token) {
+                    if is_binder(v) {
+                        break;
+                    }
+                    vars.push(v.clone());
+                    j += 1;
+                }
+                if vars.is_empty() {
+                    ast.push(Expr::variable(ident.clone()));
+                    i += 1;
+                    continue;
+                }
+                match tokens.get(j) {
+                    Some(Spanned { token: Token::Dot, .. }) => j += 1,
+                    Some(t) => return Err(ParseError::new(t.pos, "expected '.' after binder variables")),
+                    None => {
+                        return Err(ParseError::new(
+                            tokens[j - 1].pos,
+                            "expected '.' after binder variables",
+                        ))
+                    }
+                }
+                let rhs = parse_tokens(&tokens[j..])?;
+                let abs = vars
+                    .into_iter()
+                    .rev()
+                    .fold(rhs, |body, var| Expr::abstraction(var, body));
+                ast.push(abs);
+                i = tokens.len();
+            }
+            Token::Ident(ident) => {
+                ast.push(Expr::variable(ident.clone()));
+                i += 1;
+            }
+            Token::LParen => {
+                let (inner, rest) = split_group(&tokens[i + 1..], tokens[i].pos)?;
+                ast.push(parse_tokens(inner)?);
+                i = tokens.len() - rest.len();
+            }
+            Token::RParen => return Err(ParseError::new(tokens[i].pos, "unmatched ')'")),
+            Token::Dot => return Err(ParseError::new(tokens[i].pos, "unexpected '.'")),
+        }
+    }
+    ast.into_iter()
+        .reduce(Expr::application)
+        .ok_or_else(|| ParseError::new(0, "empty expression"))
+}
+
+/// Count unmatched `(` against `)` in `input`, so callers (e.g. the REPL)
+/// can tell whether more lines are needed before parsing.
+pub fn open_parens(input: &str) -> i64 {
+    input.chars().fold(0i64, |depth, c| match c {
+        '(' => depth + 1,
+        ')' => depth - 1,
+        _ => depth,
+    })
+}
+
+/// Parse a lambda term from source text, e.g. `"(\x. x) y"` or `"L f x. f (f x)"`.
+pub fn parse(input: &str) -> Result<Expr, ParseError> {
+    let tokens = tokenize(input)?;
+    parse_tokens(&tokens)
+}
+
+/// Byte ranges of every identifier token in `input`, paired with its name,
+/// in source order. Lets a caller that needs to substitute whole
+/// identifiers (e.g. the REPL's `:let` expansion) find exactly where they
+/// occur, without matching inside a longer name the way a plain substring
+/// replace would (`"I"` inside `"Id"`).
+pub fn identifier_spans(input: &str) -> Result<Vec<(std::ops::Range<usize>, String)>, ParseError> {
+    let tokens = tokenize(input)?;
+    Ok(tokens
+        .into_iter()
+        .filter_map(|t| match t.token {
+            Token::Ident(name) => Some((t.pos..t.pos + t.len, name)),
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variable() {
+        assert_eq!(parse("x").unwrap(), Expr::variable("x"));
+    }
+
+    #[test]
+    fn test_parse_application_left_assoc() {
+        // `a b c` == `((a b) c)`
+        let expected = Expr::application(
+            Expr::application(Expr::variable("a"), Expr::variable("b")),
+            Expr::variable("c"),
+        );
+        assert_eq!(parse("a b c").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_abstraction_extends_right() {
+        // `\x. a b` binds the whole `a b`, not just `a`.
+        let expected = Expr::abstraction(
+            "x",
+            Expr::application(Expr::variable("a"), Expr::variable("b")),
+        );
+        assert_eq!(parse(r"\x. a b").unwrap(), expected);
+        assert_eq!(parse("λx. a b").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_multi_arg_binder_desugars() {
+        assert_eq!(
+            parse("L f x. f (f x)").unwrap(),
+            parse(r"\f. \x. f (f x)").unwrap()
+        );
+    }
+
+    /// A bare `L` with nothing to bind isn't a binder after all — it parses
+    /// as an ordinary variable named `L`, not a "binder must be followed by
+    /// at least one variable" error.
+    #[test]
+    fn test_parse_lone_binder_token_as_variable() {
+        let expected = Expr::application(Expr::variable("f"), Expr::variable("L"));
+        assert_eq!(parse("f L").unwrap(), expected);
+    }
+
+    /// `"Id"` contains `"I"` as a substring but is a single, distinct
+    /// identifier token — `identifier_spans` must not report a match for
+    /// `"I"` at any position inside it.
+    #[test]
+    fn test_identifier_spans_does_not_match_inside_a_longer_name() {
+        let spans = identifier_spans("Id I").unwrap();
+        let names: Vec<&str> = spans.iter().map(|(_, name)| name.as_str()).collect();
+        assert_eq!(names, vec!["Id", "I"]);
+        let (range, _) = &spans[1];
+        assert_eq!(&"Id I"[range.clone()], "I");
+    }
+
+    #[test]
+    fn test_parse_round_trips_with_code() {
+        let e = Expr::application(
+            Expr::abstraction("x", Expr::variable("x")),
+            Expr::variable("y"),
+        );
+        assert_eq!(parse(&e.code()).unwrap(), e);
+    }
+
+    #[test]
+    fn test_parse_reports_position() {
+        let err = parse("(\\x. x").unwrap_err();
+        assert_eq!(err.pos, 0);
+
+        let err = parse("x )").unwrap_err();
+        assert_eq!(err.pos, 2);
+    }
+}
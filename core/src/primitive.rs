@@ -0,0 +1,275 @@
+//! Bridges the pure calculus to native Rust functions, the way rhai's
+//! `RegisterFn`/`FnCallArgs` expose host functions to its script engine: a
+//! `Registry` hands out an `Expr::Primitive` placeholder for each registered
+//! closure, and `Registry::eval` reduces a term exactly like
+//! `Expr::eval_with(Strategy::NormalOrder)` except that once a primitive has
+//! been applied to as many arguments as it expects, it decodes them, calls
+//! the native closure, and splices the (re-encoded) result back in.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crate::ast::{EvalError, Expr, PrimId, MAX_STEPS};
+
+static NEXT_PRIM_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Encode a Rust `u64` as a Church numeral: `\f. \x. f (f (... (f x)))`.
+pub fn to_church(n: u64) -> Expr {
+    let mut body = Expr::variable("x");
+    for _ in 0..n {
+        body = Expr::application(Expr::variable("f"), body);
+    }
+    Expr::abstraction("f", Expr::abstraction("x", body))
+}
+
+/// Decode a Church numeral already in normal form back into a `u64`, or
+/// `None` if `expr` isn't one.
+pub fn from_church(expr: &Expr) -> Option<u64> {
+    let Expr::Abstraction(f, body) = expr else { return None };
+    let Expr::Abstraction(x, inner) = &**body else { return None };
+
+    let mut count = 0;
+    let mut cur = &**inner;
+    loop {
+        match cur {
+            Expr::Variable(v) if v == x => return Some(count),
+            Expr::Application(applied, rest) => {
+                let Expr::Variable(v) = &**applied else { return None };
+                if v != f {
+                    return None;
+                }
+                count += 1;
+                cur = rest;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// Encode a Rust `bool` as a Church boolean: `\x.\y. x` (true) or `\x.\y. y`
+/// (false).
+pub fn to_church_bool(b: bool) -> Expr {
+    let taken = if b { "x" } else { "y" };
+    Expr::abstraction("x", Expr::abstraction("y", Expr::variable(taken)))
+}
+
+/// Decode a Church boolean already in normal form back into a `bool`, or
+/// `None` if `expr` isn't one.
+pub fn from_church_bool(expr: &Expr) -> Option<bool> {
+    let Expr::Abstraction(x, body) = expr else { return None };
+    let Expr::Abstraction(y, inner) = &**body else { return None };
+    match &**inner {
+        Expr::Variable(v) if v == x => Some(true),
+        Expr::Variable(v) if v == y => Some(false),
+        _ => None,
+    }
+}
+
+type NativeFn = dyn Fn(&[Expr]) -> Expr + Send + Sync;
+
+/// Holds the native closures registered `Expr::Primitive` ids stand in for.
+/// Cloning a `Registry` is cheap and shares the same closures, so terms
+/// carrying `Primitive` ids minted by one handle stay meaningful after the
+/// `Registry` is cloned.
+#[derive(Clone, Default)]
+pub struct Registry {
+    arity: HashMap<PrimId, usize>,
+    funcs: HashMap<PrimId, Arc<NativeFn>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Register a native closure expecting `arity` arguments, returning the
+    /// `Expr` that stands for it. Applying that `Expr` to `arity` arguments
+    /// and reducing with `Registry::eval` calls `f` with those arguments
+    /// (each already reduced to normal form) and splices in its result.
+    pub fn register(&mut self, arity: usize, f: impl Fn(&[Expr]) -> Expr + Send + Sync + 'static) -> Expr {
+        let id = PrimId(NEXT_PRIM_ID.fetch_add(1, Ordering::Relaxed));
+        self.arity.insert(id, arity);
+        self.funcs.insert(id, Arc::new(f));
+        Expr::Primitive(id)
+    }
+
+    /// Reduce `expr` to normal form, expanding any of this registry's
+    /// primitives along the way. Shorthand for the underlying `whnf`/
+    /// `eval_normal` pair, mirroring `Expr::eval`. Shares `Expr::eval`'s
+    /// `MAX_STEPS` budget, cut off the same way and reported the same way,
+    /// so a primitive whose arguments never reach normal form (or a plain
+    /// non-terminating term run through here) fails gracefully instead of
+    /// looping forever.
+    pub fn eval(&self, expr: &Expr) -> Result<Expr, EvalError> {
+        let mut steps = 0;
+        let reduced = self.eval_normal(expr, &mut steps);
+        if steps >= MAX_STEPS {
+            Err(EvalError::StepLimitExceeded { steps, partial: reduced })
+        } else {
+            Ok(reduced)
+        }
+    }
+
+    fn eval_normal(&self, expr: &Expr, steps: &mut u32) -> Expr {
+        use Expr::*;
+        match self.whnf(expr, steps) {
+            Abstraction(var, body) => Abstraction(var, Box::new(self.eval_normal(&body, steps))),
+            Application(e1, e2) => {
+                Application(Box::new(self.eval_normal(&e1, steps)), Box::new(self.eval_normal(&e2, steps)))
+            }
+            other => other,
+        }
+    }
+
+    /// Reduce to weak head normal form, expanding a saturated primitive in
+    /// place whenever the head of an application settles on one. Loops
+    /// across successive redexes (including a primitive's own expansion)
+    /// rather than recursing, for the same reason as `Expr::whnf`: so a
+    /// long-running reduction doesn't also blow the call stack.
+    fn whnf(&self, expr: &Expr, steps: &mut u32) -> Expr {
+        use Expr::*;
+        let mut current = expr.clone();
+        loop {
+            match current {
+                Application(e1, e2) => match self.whnf(&e1, steps) {
+                    Abstraction(var, body) => {
+                        if *steps >= MAX_STEPS {
+                            return Application(Box::new(Abstraction(var, body)), e2);
+                        }
+                        *steps += 1;
+                        current = body.subst(&var, &e2);
+                    }
+                    head => {
+                        let applied = Application(Box::new(head), e2);
+                        if *steps >= MAX_STEPS {
+                            return applied;
+                        }
+                        match self.apply_if_saturated(&applied, steps) {
+                            Some(reduced) => {
+                                *steps += 1;
+                                current = reduced;
+                            }
+                            None => return applied,
+                        }
+                    }
+                },
+                other => return other,
+            }
+        }
+    }
+
+    /// If `applied`'s application spine is a primitive applied to exactly
+    /// as many arguments as it was registered with, call the native closure
+    /// on the (normalized) arguments and reduce its result; otherwise `None`.
+    fn apply_if_saturated(&self, applied: &Expr, steps: &mut u32) -> Option<Expr> {
+        let (id, args) = spine_primitive(applied)?;
+        let arity = *self.arity.get(&id)?;
+        if args.len() != arity {
+            return None;
+        }
+        let normal_args: Vec<Expr> = args.iter().map(|a| self.eval_normal(a, steps)).collect();
+        let func = self.funcs.get(&id)?;
+        Some(self.eval_normal(&func(&normal_args), steps))
+    }
+}
+
+/// If `expr`'s application spine is headed by a `Primitive`, return its id
+/// and the arguments applied to it, outermost last — e.g. `(p a) b` gives
+/// `(p, [a, b])`.
+fn spine_primitive(expr: &Expr) -> Option<(PrimId, Vec<Expr>)> {
+    match expr {
+        Expr::Primitive(id) => Some((*id, vec![])),
+        Expr::Application(f, x) => {
+            let (id, mut args) = spine_primitive(f)?;
+            args.push((**x).clone());
+            Some((id, args))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_church_numeral_round_trip() {
+        assert_eq!(from_church(&to_church(0)), Some(0));
+        assert_eq!(from_church(&to_church(1)), Some(1));
+        assert_eq!(from_church(&to_church(5)), Some(5));
+    }
+
+    #[test]
+    fn test_church_bool_round_trip() {
+        assert_eq!(from_church_bool(&to_church_bool(true)), Some(true));
+        assert_eq!(from_church_bool(&to_church_bool(false)), Some(false));
+    }
+
+    #[test]
+    fn test_registry_applies_saturated_primitive() {
+        let mut registry = Registry::new();
+        let add = registry.register(2, |args| {
+            let a = from_church(&args[0]).expect("numeral");
+            let b = from_church(&args[1]).expect("numeral");
+            to_church(a + b)
+        });
+
+        let term = Expr::application(
+            Expr::application(add, to_church(2)),
+            to_church(3),
+        );
+        assert_eq!(from_church(&registry.eval(&term).unwrap()), Some(5));
+    }
+
+    #[test]
+    fn test_registry_leaves_partial_application_unreduced() {
+        let mut registry = Registry::new();
+        let add = registry.register(2, |args| {
+            let a = from_church(&args[0]).expect("numeral");
+            let b = from_church(&args[1]).expect("numeral");
+            to_church(a + b)
+        });
+
+        // Only one of two expected arguments: stays a neutral application.
+        let term = Expr::application(add, to_church(2));
+        match registry.eval(&term).unwrap() {
+            Expr::Application(_, _) => {}
+            other => panic!("expected a partially-applied primitive, got {}", other.code()),
+        }
+    }
+
+    #[test]
+    fn test_registry_primitive_under_a_binder() {
+        // (\x. add x 1) 4 --> 5, exercising substitution through a
+        // primitive that's only saturated once `x` is replaced.
+        let mut registry = Registry::new();
+        let add = registry.register(2, |args| {
+            let a = from_church(&args[0]).expect("numeral");
+            let b = from_church(&args[1]).expect("numeral");
+            to_church(a + b)
+        });
+
+        let body = Expr::application(Expr::application(add, Expr::variable("x")), to_church(1));
+        let func = Expr::abstraction("x", body);
+        let term = Expr::application(func, to_church(4));
+
+        assert_eq!(from_church(&registry.eval(&term).unwrap()), Some(5));
+    }
+
+    /// `(\x. x x)(\x. x x)` never reaches a normal form; the step cap must
+    /// surface `EvalError::StepLimitExceeded` instead of hanging and
+    /// eventually overflowing the stack, exactly like `ast::tests::
+    /// test_step_limit_exceeded_is_an_error`.
+    #[test]
+    fn test_registry_eval_step_limit_terminates() {
+        let registry = Registry::new();
+        let omega_body = Expr::abstraction("x", Expr::application(Expr::variable("x"), Expr::variable("x")));
+        let omega = Expr::application(omega_body.clone(), omega_body);
+
+        match registry.eval(&omega) {
+            Err(EvalError::StepLimitExceeded { steps, .. }) => assert_eq!(steps, MAX_STEPS),
+            Ok(reduced) => panic!("expected a step-limit error, got {}", reduced.code()),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Hindley-Milner type inference over lambda terms, via Algorithm W: walk
+//! the term once, generating a fresh type variable at every binder and
+//! application, and unify as you go against a substitution map shared for
+//! the whole pass. Every term that passes has a principal (most general)
+//! type; a term like `\x. x x` is rejected because the occurs-check refuses
+//! to unify a type variable with an arrow type that contains it.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::ast::Expr;
+
+static FRESH_TYPE_VAR: AtomicU32 = AtomicU32::new(0);
+
+fn fresh_type_var() -> Type {
+    Type::Var(FRESH_TYPE_VAR.fetch_add(1, Ordering::Relaxed))
+}
+
+/// An inferred type: either a type variable awaiting unification, or a
+/// function type from one type to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    Var(u32),
+    Arrow(Box<Type>, Box<Type>),
+}
+
+impl Type {
+    /// Render this type with its variables renamed to `a, b, c, …` in order
+    /// of first appearance, for printing alongside `Expr::code`.
+    pub fn pretty(&self) -> String {
+        let mut names = HashMap::new();
+        self.pretty_inner(&mut names)
+    }
+
+    fn pretty_inner(&self, names: &mut HashMap<u32, String>) -> String {
+        match self {
+            Type::Var(n) => {
+                let next = names.len() as u32;
+                names
+                    .entry(*n)
+                    .or_insert_with(|| {
+                        let letter = (b'a' + (next % 26) as u8) as char;
+                        let suffix = next / 26;
+                        if suffix == 0 {
+                            letter.to_string()
+                        } else {
+                            format!("{letter}{suffix}")
+                        }
+                    })
+                    .clone()
+            }
+            Type::Arrow(from, to) => format!("({} -> {})", from.pretty_inner(names), to.pretty_inner(names)),
+        }
+    }
+}
+
+/// Why `infer` failed to assign a term a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeError {
+    /// A variable occurred free with no binder in scope to give it a type.
+    UnboundVariable(String),
+    /// Unifying `.0` with `.1` would require an infinite type, e.g. from
+    /// self-application (`\x. x x`).
+    OccursCheck(Type, Type),
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeError::UnboundVariable(name) => write!(f, "unbound variable '{name}'"),
+            TypeError::OccursCheck(a, b) => write!(
+                f,
+                "occurs check failed: {} ~ {} would create an infinite type",
+                a.pretty(),
+                b.pretty()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Type variable bindings discovered so far, shared across the whole
+/// inference pass.
+type Substitution = HashMap<u32, Type>;
+
+/// Maps a term variable's name to the type it was bound with.
+type Env = HashMap<String, Type>;
+
+/// Follow `ty` through `subst` until it's no longer a bound type variable.
+fn resolve(ty: &Type, subst: &Substitution) -> Type {
+    match ty {
+        Type::Var(n) => match subst.get(n) {
+            Some(bound) => resolve(bound, subst),
+            None => ty.clone(),
+        },
+        Type::Arrow(from, to) => Type::Arrow(
+            Box::new(resolve(from, subst)),
+            Box::new(resolve(to, subst)),
+        ),
+    }
+}
+
+/// Does type variable `var` occur anywhere in `ty` (after resolving
+/// substitutions)? Used to reject infinite types before they're created.
+fn occurs(var: u32, ty: &Type, subst: &Substitution) -> bool {
+    match resolve(ty, subst) {
+        Type::Var(n) => n == var,
+        Type::Arrow(from, to) => occurs(var, &from, subst) || occurs(var, &to, subst),
+    }
+}
+
+/// Unify `a` and `b`, recording any new type variable bindings in `subst`.
+fn unify(a: &Type, b: &Type, subst: &mut Substitution) -> Result<(), TypeError> {
+    let a = resolve(a, subst);
+    let b = resolve(b, subst);
+    match (&a, &b) {
+        (Type::Var(n1), Type::Var(n2)) if n1 == n2 => Ok(()),
+        (Type::Var(n), _) => {
+            if occurs(*n, &b, subst) {
+                Err(TypeError::OccursCheck(a, b))
+            } else {
+                subst.insert(*n, b);
+                Ok(())
+            }
+        }
+        (_, Type::Var(n)) => {
+            if occurs(*n, &a, subst) {
+                Err(TypeError::OccursCheck(a, b))
+            } else {
+                subst.insert(*n, a);
+                Ok(())
+            }
+        }
+        (Type::Arrow(a1, a2), Type::Arrow(b1, b2)) => {
+            unify(a1, b1, subst)?;
+            unify(a2, b2, subst)
+        }
+    }
+}
+
+fn infer_rec(expr: &Expr, env: &Env, subst: &mut Substitution) -> Result<Type, TypeError> {
+    match expr {
+        Expr::Variable(v) => env
+            .get(&v.name)
+            .cloned()
+            .ok_or_else(|| TypeError::UnboundVariable(v.name.clone())),
+        Expr::Abstraction(v, body) => {
+            let var_ty = fresh_type_var();
+            let mut inner_env = env.clone();
+            inner_env.insert(v.name.clone(), var_ty.clone());
+            let body_ty = infer_rec(body, &inner_env, subst)?;
+            Ok(Type::Arrow(Box::new(resolve(&var_ty, subst)), Box::new(body_ty)))
+        }
+        Expr::Application(f, x) => {
+            let f_ty = infer_rec(f, env, subst)?;
+            let x_ty = infer_rec(x, env, subst)?;
+            let result_ty = fresh_type_var();
+            unify(&f_ty, &Type::Arrow(Box::new(x_ty), Box::new(result_ty.clone())), subst)?;
+            Ok(resolve(&result_ty, subst))
+        }
+        // A registered primitive's real type lives with its `Registry`
+        // entry, not in the term itself; give it a fresh type variable like
+        // any other not-yet-constrained value.
+        Expr::Primitive(_) => Ok(fresh_type_var()),
+    }
+}
+
+impl Expr {
+    /// Infer this term's principal type via Algorithm W, or the `TypeError`
+    /// that ruled it out. Free variables are rejected as unbound rather than
+    /// assigned some catch-all type, so `infer` only succeeds on closed
+    /// terms.
+    pub fn infer(&self) -> Result<Type, TypeError> {
+        let mut subst = Substitution::new();
+        let ty = infer_rec(self, &Env::new(), &mut subst)?;
+        Ok(resolve(&ty, &subst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_identity() {
+        // \x. x : a -> a
+        let id = Expr::abstraction("x", Expr::variable("x"));
+        match id.infer().unwrap() {
+            Type::Arrow(from, to) => assert_eq!(from, to),
+            other => panic!("expected an arrow type, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_infer_const() {
+        // \x. \y. x : a -> (b -> a)
+        let k = Expr::abstraction("x", Expr::abstraction("y", Expr::variable("x")));
+        match k.infer().unwrap() {
+            Type::Arrow(a, inner) => match *inner {
+                Type::Arrow(_, a2) => assert_eq!(a, a2),
+                other => panic!("expected an arrow type, got {other:?}"),
+            },
+            other => panic!("expected an arrow type, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_infer_application() {
+        // (\x. x) applied to a free variable `y` is ill-typed only because
+        // `y` is unbound, not because of the application itself.
+        let id = Expr::abstraction("x", Expr::variable("x"));
+        let applied = Expr::application(id, Expr::variable("y"));
+        match applied.infer() {
+            Err(TypeError::UnboundVariable(name)) => assert_eq!(name, "y"),
+            other => panic!("expected an unbound-variable error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_infer_closed_application() {
+        // (\x. \y. x) applied twice to two closed abstractions is well-typed.
+        let k = Expr::abstraction("x", Expr::abstraction("y", Expr::variable("x")));
+        let a = Expr::abstraction("z", Expr::variable("z"));
+        let b = Expr::abstraction("z", Expr::variable("z"));
+        let term = Expr::application(Expr::application(k, a), b);
+        assert!(term.infer().is_ok());
+    }
+
+    /// `\x. x x` applies `x` to itself, which would require `x`'s type to be
+    /// both `a` and `a -> b` for the same `a` — an infinite type, rejected
+    /// by the occurs-check.
+    #[test]
+    fn test_infer_rejects_self_application() {
+        let omega_body = Expr::abstraction(
+            "x",
+            Expr::application(Expr::variable("x"), Expr::variable("x")),
+        );
+        match omega_body.infer() {
+            Err(TypeError::OccursCheck(_, _)) => {}
+            other => panic!("expected an occurs-check error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_pretty_names_type_variables() {
+        let id = Expr::abstraction("x", Expr::variable("x"));
+        assert_eq!(id.infer().unwrap().pretty(), "(a -> a)");
+    }
+}
@@ -1,34 +1,165 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use proc_macro::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream, TokenTree};
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
 
-use anyhow::{anyhow, bail, Result};
-
-use lambdars_core::ast::{Expr, Var};
+use lambdars_core::ast::{EvalError, Expr, Var};
 
 const LAMBDA_TOK: &str = "L";
 
-fn astize(tokens: &[TokenTree]) -> Result<Expr> {
-    let mut ast: Vec<Expr> = vec![];
+/// A parse error tied to the source span that caused it, so the macro can
+/// report a `compile_error!` underlining the offending lambda, paren, or
+/// undecorated variable instead of the whole macro invocation.
+struct MacroError {
+    span: Span,
+    message: String,
+}
+
+impl MacroError {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        MacroError {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// Render this error as a `compile_error!("...")` invocation whose tokens
+    /// all carry `self.span`, so rustc squiggles the exact token at fault.
+    fn into_tokens(self) -> TokenStream {
+        let span = self.span;
+        let mut message = Literal::string(&self.message);
+        message.set_span(span);
+
+        let mut path = Ident::new("compile_error", span);
+        path.set_span(span);
+
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(span);
+
+        let mut args = Group::new(Delimiter::Parenthesis, TokenStream::from(TokenTree::Literal(message)));
+        args.set_span(span);
+
+        let mut semi = Punct::new(';', Spacing::Alone);
+        semi.set_span(span);
+
+        TokenStream::from_iter([
+            TokenTree::Ident(path),
+            TokenTree::Punct(bang),
+            TokenTree::Group(args),
+            TokenTree::Punct(semi),
+        ])
+    }
+}
+
+type MacroResult<T> = Result<T, MacroError>;
+
+/// An `Expr` node paired with the source span it was parsed from. Kept
+/// separate from `lambdars_core::ast::Expr` so the evaluator and pretty
+/// printer never have to know about `proc_macro::Span`.
+#[derive(Debug, Clone)]
+enum SpannedExpr {
+    Variable(Span, String),
+    Abstraction(Span, String, Box<SpannedExpr>),
+    Application(Span, Box<SpannedExpr>, Box<SpannedExpr>),
+}
+
+impl SpannedExpr {
+    fn span(&self) -> Span {
+        match self {
+            SpannedExpr::Variable(s, _)
+            | SpannedExpr::Abstraction(s, _, _)
+            | SpannedExpr::Application(s, _, _) => *s,
+        }
+    }
+
+    /// Drop span information to get the plain `Expr` that `eval`/`code` use.
+    fn strip(&self) -> Expr {
+        match self {
+            SpannedExpr::Variable(_, name) => Expr::variable(name),
+            SpannedExpr::Abstraction(_, var, body) => Expr::abstraction(var, body.strip()),
+            SpannedExpr::Application(_, e1, e2) => Expr::application(e1.strip(), e2.strip()),
+        }
+    }
+
+    /// Record the span of the first occurrence of every variable name, so
+    /// that errors discovered *after* evaluation (when only variable names
+    /// survive, not spans) can still point at source.
+    fn collect_spans(&self, spans: &mut HashMap<String, Span>) {
+        match self {
+            SpannedExpr::Variable(span, name) => {
+                spans.entry(name.clone()).or_insert(*span);
+            }
+            SpannedExpr::Abstraction(_, _, body) => body.collect_spans(spans),
+            SpannedExpr::Application(_, e1, e2) => {
+                e1.collect_spans(spans);
+                e2.collect_spans(spans);
+            }
+        }
+    }
+}
+
+/// Is `ident` a binder keyword? Recognized only as a whole token (`L` or
+/// `λ`), never as a prefix, so an ordinary variable named `List` or `Left`
+/// is just a variable.
+fn is_binder(ident: &str) -> bool {
+    ident == LAMBDA_TOK || ident == "λ"
+}
+
+fn astize(tokens: &[TokenTree]) -> MacroResult<SpannedExpr> {
+    let mut ast: Vec<SpannedExpr> = vec![];
     let mut iter = tokens.iter().peekable();
     while let Some(token) = iter.next() {
         match token {
-            TokenTree::Ident(raw) => {
-                let ident = raw.to_string();
-                if ident.starts_with(LAMBDA_TOK) {
-                    let variable = &ident[1..];
-                    // Collect tokens for the right-hand side
-                    let rhs_tokens: Vec<_> = iter.by_ref().cloned().collect();
-                    let rhs = astize(&rhs_tokens)?;
-                    let abs = Expr::abstraction(variable, rhs);
-                    ast.push(abs);
-                } else {
-                    ast.push(Expr::variable(ident));
+            TokenTree::Ident(raw) if is_binder(&raw.to_string()) => {
+                let binder_span = raw.span();
+
+                // One or more binder variables precede the `.`; `L x y. e`
+                // desugars to nested abstractions `L x. L y. e`. If none
+                // follow, this wasn't a binder after all — treat the lone
+                // `L`/`λ` token as an ordinary variable instead of erroring,
+                // so `f L` (applying `f` to a variable named `L`) parses,
+                // sharing this fallback with `core::parser`'s runtime parser.
+                let mut vars = vec![];
+                loop {
+                    match iter.peek() {
+                        Some(TokenTree::Ident(v)) if !is_binder(&v.to_string()) => {
+                            vars.push(v.to_string());
+                            iter.next();
+                        }
+                        _ => break,
+                    }
+                }
+                if vars.is_empty() {
+                    ast.push(SpannedExpr::Variable(binder_span, raw.to_string()));
+                    continue;
+                }
+                match iter.peek() {
+                    Some(TokenTree::Punct(p)) if p.as_char() == '.' => {
+                        iter.next();
+                    }
+                    Some(other) => {
+                        return Err(MacroError::new(other.span(), "expected '.' after binder variables"))
+                    }
+                    None => {
+                        return Err(MacroError::new(binder_span, "expected '.' after binder variables"))
+                    }
                 }
+
+                // Everything else in this group is the abstraction body: the
+                // body extends as far right as the enclosing group allows.
+                let rhs_tokens: Vec<_> = iter.by_ref().cloned().collect();
+                let rhs = astize(&rhs_tokens)?;
+                let abs = vars
+                    .into_iter()
+                    .rev()
+                    .fold(rhs, |body, var| SpannedExpr::Abstraction(binder_span, var, Box::new(body)));
+                ast.push(abs);
+            }
+            TokenTree::Ident(raw) => {
+                ast.push(SpannedExpr::Variable(raw.span(), raw.to_string()));
             }
             TokenTree::Group(group) => {
                 if group.delimiter() != Delimiter::Parenthesis {
-                    bail!("invalid delimiter for abstraction");
+                    return Err(MacroError::new(group.span(), "invalid delimiter for abstraction"));
                 }
                 let inner_tokens: Vec<_> = group.stream().into_iter().collect();
                 let inner = astize(&inner_tokens)?;
@@ -38,15 +169,16 @@ fn astize(tokens: &[TokenTree]) -> Result<Expr> {
         }
     }
 
-    // Apply in left-most order
-    let expr = ast
-        .into_iter()
-        .reduce(|e, n| Expr::application(e, n))
-        .expect("empty expression");
-    Ok(expr)
+    // Application is left-associative and binds tighter than abstraction.
+    ast.into_iter()
+        .reduce(|e, n| {
+            let span = e.span();
+            SpannedExpr::Application(span, Box::new(e), Box::new(n))
+        })
+        .ok_or_else(|| MacroError::new(Span::call_site(), "empty expression"))
 }
 
-fn collect_inputs(tokens: TokenStream) -> Result<HashSet<String>> {
+fn collect_inputs(tokens: TokenStream) -> MacroResult<HashSet<String>> {
     let mut inputs = HashSet::new();
     for token in tokens {
         match token {
@@ -55,72 +187,223 @@ fn collect_inputs(tokens: TokenStream) -> Result<HashSet<String>> {
             }
             TokenTree::Punct(punct_symbol) => {
                 if punct_symbol.as_char() != ',' {
-                    return Err(anyhow!(
-                        "invalid character '{}' in input decorator",
-                        punct_symbol.as_char()
+                    return Err(MacroError::new(
+                        punct_symbol.span(),
+                        format!("invalid character '{}' in input decorator", punct_symbol.as_char()),
                     ));
                 }
             }
-            _ => return Err(anyhow!("invalid input decorator")),
+            other => return Err(MacroError::new(other.span(), "invalid input decorator")),
         }
     }
     Ok(inputs)
 }
 
-fn handle_io(tokens: &[TokenTree]) -> Result<(&[TokenTree], HashSet<String>)> {
-    // Iterate with index and pattern match
-    for (i, token) in tokens.iter().enumerate() {
-        if let TokenTree::Punct(symbol) = token {
-            if symbol.as_char() == '@' {
-                if let Some(TokenTree::Ident(raw)) = tokens.get(i + 1) {
-                    let ident = raw.to_string();
-                    if ident != "input" {
-                        bail!("invalid decorator '{ident}'");
+/// Which Church-encoded shape (if any) the caller asked `construct_output`
+/// to decode the reduced term into, via an `@output(...)` decorator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputHint {
+    /// No decorator given: try every known shape, then fall back to the
+    /// existing captured-variable/tuple behavior.
+    Auto,
+    Bool,
+    Num,
+}
+
+/// Parse an optional `@output(bool)` / `@output(u32)` decorator immediately
+/// preceding the expression, mirroring `@input(...)`.
+fn handle_output(tokens: &[TokenTree]) -> MacroResult<(&[TokenTree], OutputHint)> {
+    match tokens {
+        [TokenTree::Punct(at), TokenTree::Ident(raw), TokenTree::Group(group), rest @ ..]
+            if at.as_char() == '@' && raw.to_string() == "output" =>
+        {
+            if group.delimiter() != Delimiter::Parenthesis {
+                return Err(MacroError::new(group.span(), "invalid delimiter for output decorator"));
+            }
+            let inner: Vec<_> = group.stream().into_iter().collect();
+            let hint = match inner.as_slice() {
+                [TokenTree::Ident(kind)] => match kind.to_string().as_str() {
+                    "bool" => OutputHint::Bool,
+                    "u32" => OutputHint::Num,
+                    other => {
+                        return Err(MacroError::new(kind.span(), format!("unknown output type '{other}'")))
                     }
-                    if let Some(TokenTree::Group(group)) = tokens.get(i + 2) {
-                        if group.delimiter() != Delimiter::Parenthesis {
-                            bail!("invalid delimiter for input decorator");
-                        }
-                        // Collect inputs from the group stream
-                        let inputs = collect_inputs(group.stream())?;
-                        return Ok((&tokens[i + 3..], inputs));
-                    } else {
-                        bail!("invalid decorator");
+                },
+                _ => return Err(MacroError::new(group.span(), "expected a single output type")),
+            };
+            Ok((rest, hint))
+        }
+        _ => Ok((tokens, OutputHint::Auto)),
+    }
+}
+
+/// Parse an optional `@input(...)` decorator immediately preceding the
+/// expression. A leading `@output` is left alone here for `handle_io`'s
+/// caller to handle next; any other decorator besides `@input` is an error.
+fn handle_io(tokens: &[TokenTree]) -> MacroResult<(&[TokenTree], HashSet<String>)> {
+    match tokens {
+        [TokenTree::Punct(at), TokenTree::Ident(raw), rest @ ..] if at.as_char() == '@' => {
+            let ident = raw.to_string();
+            if ident == "output" {
+                return Ok((tokens, HashSet::new()));
+            }
+            if ident != "input" {
+                return Err(MacroError::new(raw.span(), format!("invalid decorator '{ident}'")));
+            }
+            match rest {
+                [TokenTree::Group(group), rest @ ..] => {
+                    if group.delimiter() != Delimiter::Parenthesis {
+                        return Err(MacroError::new(group.span(), "invalid delimiter for input decorator"));
                     }
-                } else {
-                    bail!("invalid decorator");
+                    let inputs = collect_inputs(group.stream())?;
+                    Ok((rest, inputs))
                 }
+                _ => Err(MacroError::new(raw.span(), "invalid decorator")),
             }
         }
+        _ => Ok((tokens, HashSet::new())),
     }
-    Ok((tokens, HashSet::new()))
 }
 
 #[proc_macro]
 pub fn lambda(body: TokenStream) -> TokenStream {
+    match expand(body) {
+        Ok(tokens) => tokens,
+        Err(err) => err.into_tokens(),
+    }
+}
+
+fn expand(body: TokenStream) -> MacroResult<TokenStream> {
     let tokens = body.into_iter().collect::<Vec<_>>();
-    let (tokens, inputs) = handle_io(&tokens).unwrap();
-    let expr = astize(&tokens).unwrap();
-    let reduced = expr.eval();
+    let (tokens, inputs) = handle_io(&tokens)?;
+    let (tokens, hint) = handle_output(tokens)?;
+    let spanned = astize(tokens)?;
 
-    println!("{} --> {}", expr.code(), reduced.code());
+    let mut spans = HashMap::new();
+    spanned.collect_spans(&mut spans);
 
-    match construct_output(&reduced, &inputs).unwrap() {
-        Some(output) => output,
-        None => panic!(
-            "expression reduced to '{}' which is not a valid output type",
-            reduced.code()
+    let expr = spanned.strip();
+    let reduced = expr.eval().map_err(|err| match err {
+        EvalError::StepLimitExceeded { steps, partial } => MacroError::new(
+            spanned.span(),
+            format!(
+                "term did not reduce to a normal form within {steps} steps (stuck at '{}')",
+                partial.code()
+            ),
         ),
+    })?;
+
+    println!("{} --> {}", expr.code(), reduced.code());
+
+    construct_output(&reduced, &inputs, hint, &spans)?
+        .ok_or_else(|| {
+            MacroError::new(
+                spanned.span(),
+                format!(
+                    "expression reduced to '{}' which is not a valid output type",
+                    reduced.code()
+                ),
+            )
+        })
+}
+
+/// Decode a Church boolean: `λx.λy.x` → `true`, `λx.λy.y` → `false`.
+fn decode_bool(expr: &Expr) -> Option<bool> {
+    let Expr::Abstraction(x, body) = expr else { return None };
+    let Expr::Abstraction(y, inner) = &**body else { return None };
+    match &**inner {
+        Expr::Variable(v) if v == x => Some(true),
+        Expr::Variable(v) if v == y => Some(false),
+        _ => None,
+    }
+}
+
+/// Decode a Church numeral `λf.λx. f (f (... (f x)))` as the depth of
+/// left-nested applications of `f` to `x`.
+fn decode_numeral(expr: &Expr) -> Option<u32> {
+    let Expr::Abstraction(f, body) = expr else { return None };
+    let Expr::Abstraction(x, inner) = &**body else { return None };
+
+    let mut count = 0;
+    let mut cur = &**inner;
+    loop {
+        match cur {
+            Expr::Variable(v) if v == x => return Some(count),
+            Expr::Application(applied, rest) => {
+                let Expr::Variable(v) = &**applied else { return None };
+                if v != f {
+                    return None;
+                }
+                count += 1;
+                cur = rest;
+            }
+            _ => return None,
+        }
     }
 }
 
-/// Convert variable-only applications to a nested tuple.
-fn construct_output(expr: &Expr, valid_outputs: &HashSet<String>) -> Result<Option<TokenStream>> {
+/// Decode a Church pair `λf. f A B` into a `(A, B)` tuple, decoding `A` and
+/// `B` recursively (they may themselves be Church-encoded values).
+fn decode_pair(
+    expr: &Expr,
+    valid_outputs: &HashSet<String>,
+    hint: OutputHint,
+    spans: &HashMap<String, Span>,
+) -> MacroResult<Option<TokenStream>> {
+    let Expr::Abstraction(f, body) = expr else { return Ok(None) };
+    let Expr::Application(fa, b) = &**body else { return Ok(None) };
+    let Expr::Application(applied, a) = &**fa else { return Ok(None) };
+    let Expr::Variable(v) = &**applied else { return Ok(None) };
+    if v != f {
+        return Ok(None);
+    }
+
+    let (Some(a_ts), Some(b_ts)) = (
+        construct_output(a, valid_outputs, hint, spans)?,
+        construct_output(b, valid_outputs, hint, spans)?,
+    ) else {
+        return Ok(None);
+    };
+
+    let tuple_inner = TokenStream::from_iter([
+        a_ts.into_iter().next().expect("non-empty output"),
+        TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        b_ts.into_iter().next().expect("non-empty output"),
+    ]);
+    let tuple = TokenTree::Group(Group::new(Delimiter::Parenthesis, tuple_inner));
+    Ok(Some(TokenStream::from(tuple)))
+}
+
+/// Convert variable-only applications to a nested tuple, or, if `hint`
+/// requests it (or in `Auto` mode, if the shape matches), decode a Church
+/// boolean/numeral/pair into the corresponding Rust literal.
+fn construct_output(
+    expr: &Expr,
+    valid_outputs: &HashSet<String>,
+    hint: OutputHint,
+    spans: &HashMap<String, Span>,
+) -> MacroResult<Option<TokenStream>> {
     match expr {
-        Expr::Abstraction(_, _) => Ok(None),
+        Expr::Abstraction(_, _) => {
+            if matches!(hint, OutputHint::Auto | OutputHint::Bool) {
+                if let Some(b) = decode_bool(expr) {
+                    let ident = if b { "true" } else { "false" };
+                    return Ok(Some(TokenStream::from(TokenTree::Ident(Ident::new(
+                        ident,
+                        Span::call_site(),
+                    )))));
+                }
+            }
+            if matches!(hint, OutputHint::Auto | OutputHint::Num) {
+                if let Some(n) = decode_numeral(expr) {
+                    return Ok(Some(TokenStream::from(TokenTree::Literal(Literal::u32_suffixed(n)))));
+                }
+            }
+            decode_pair(expr, valid_outputs, hint, spans)
+        }
         Expr::Application(e1, e2) => {
-            let l_var = extract_valid_output(e1, valid_outputs)?;
-            let r_var = extract_valid_output(e2, valid_outputs)?;
+            let l_var = extract_valid_output(e1, valid_outputs, hint, spans)?;
+            let r_var = extract_valid_output(e2, valid_outputs, hint, spans)?;
 
             let tuple_inner = TokenStream::from_iter([
                 l_var,
@@ -132,20 +415,34 @@ fn construct_output(expr: &Expr, valid_outputs: &HashSet<String>) -> Result<Opti
         }
         Expr::Variable(Var { name, .. }) => {
             if valid_outputs.contains(name) {
-                let ident = TokenTree::Ident(Ident::new(name, Span::call_site()));
+                let span = spans.get(name).copied().unwrap_or_else(Span::call_site);
+                let ident = TokenTree::Ident(Ident::new(name, span));
                 Ok(Some(TokenStream::from(ident)))
             } else {
-                bail!("expression reduction contains '{name}', which is not decorated as an input")
+                let span = spans.get(name).copied().unwrap_or_else(Span::call_site);
+                Err(MacroError::new(
+                    span,
+                    format!("expression reduction contains '{name}', which is not decorated as an input"),
+                ))
             }
         }
+        // `lambda!` terms are built purely from macro tokens, so a
+        // `Primitive` (only ever introduced via `lambdars_core::primitive`)
+        // can't appear in a reduced term here.
+        Expr::Primitive(_) => Ok(None),
     }
 }
 
 // Helper function to extract valid output or bail if not present.
-fn extract_valid_output(expr: &Expr, valid_outputs: &HashSet<String>) -> Result<TokenTree> {
-    construct_output(expr, valid_outputs)?
-        .ok_or(anyhow!("expected a valid output type"))?
+fn extract_valid_output(
+    expr: &Expr,
+    valid_outputs: &HashSet<String>,
+    hint: OutputHint,
+    spans: &HashMap<String, Span>,
+) -> MacroResult<TokenTree> {
+    construct_output(expr, valid_outputs, hint, spans)?
+        .ok_or_else(|| MacroError::new(Span::call_site(), "expected a valid output type"))?
         .into_iter()
         .next()
-        .ok_or(anyhow!("expected a valid output type"))
+        .ok_or_else(|| MacroError::new(Span::call_site(), "expected a valid output type"))
 }
@@ -18,7 +18,7 @@ fn test_swap() {
     let t = lambda! {
         @input(a, b) // capture `a` and `b` from outer scope
 
-        (Lx.Ly. y x) a b   // swap
+        (L x y. y x) a b   // swap
     };
     assert_eq!(t, ("bbb", "aaa"));
 }
@@ -29,7 +29,7 @@ fn test_copy() {
 
     let t = lambda! {
         @input(a)
-        (Lx.x x) a
+        (L x. x x) a
     };
     assert_eq!(t, ("aaa", "aaa"));
 }
@@ -41,7 +41,7 @@ fn test_nesting() {
     let c = "ccc";
     let t = lambda! {
         @input(a, b, c)
-        (Lx.Ly. y x) a b c
+        (L x y. y x) a b c
     };
     assert_eq!(t, ((2, 1), "ccc"));
 }
@@ -52,7 +52,7 @@ fn test_complex() {
     let out = lambda! {
         @input(t)
 
-        (Lx.(Ly.x y)(Lz.z))(La.a a) t
+        (L x. (L y. x y)(L z. z))(L a. a a) t
     };
     assert_eq!(out, t);
 }
@@ -63,17 +63,80 @@ fn test_not() {
     let b = 2;
     let not_true = lambda! {
         @input(a, b)
-        (Lt. (t (Lx.Ly.y) (Lx.Ly.x)))   // NOT gate
-            (Lx.Ly.x) a b               // call the NOT gate with TRUE
+        (L t. (t (L x y. y) (L x y. x)))   // NOT gate
+            (L x y. x) a b               // call the NOT gate with TRUE
     };
     // NOT(TRUE) --> FALSE, and (FALSE a b) --> b
     assert_eq!(not_true, b);
 
     let not_false = lambda! {
         @input(a, b)
-        (Lt. (t (Lx.Ly.y) (Lx.Ly.x)))   // NOT gate
-            (Lx.Ly.y) a b               // call the NOT gate with FALSE
+        (L t. (t (L x y. y) (L x y. x)))   // NOT gate
+            (L x y. y) a b               // call the NOT gate with FALSE
     };
     // NOT(FALSE) --> TRUE, and (TRUE a b) --> a
     assert_eq!(not_false, a);
 }
+
+#[test]
+#[allow(non_snake_case)]
+fn test_variable_name_collision() {
+    // `List` starts with the binder letter `L` but is just a variable now
+    // that the binder must stand alone as its own token.
+    let List = "not a binder";
+
+    let t = lambda! {
+        @input(List)
+        (L x. x) List
+    };
+    assert_eq!(t, List);
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_lone_binder_token_as_variable() {
+    // The second `L` here has no variables left to bind before the
+    // expression ends, so it isn't a binder after all — just a variable
+    // named `L`, same as `core::parser`'s runtime parser treats it.
+    let L = "not a binder either";
+
+    let t = lambda! {
+        @input(L)
+        (L x. x) L
+    };
+    assert_eq!(t, L);
+}
+
+#[test]
+fn test_church_bool() {
+    let t = lambda! {
+        @output(bool)
+        L x y. x
+    };
+    assert!(t);
+
+    let f = lambda! {
+        @output(bool)
+        L x y. y
+    };
+    assert!(!f);
+}
+
+#[test]
+fn test_church_numeral() {
+    let three = lambda! {
+        @output(u32)
+        L f x. f (f (f x))
+    };
+    assert_eq!(three, 3);
+}
+
+#[test]
+fn test_church_pair() {
+    // A pair of Church numerals (2, 1).
+    let pair = lambda! {
+        @output(u32)
+        L f. f (L f x. f (f x)) (L f x. f x)
+    };
+    assert_eq!(pair, (2, 1));
+}
@@ -0,0 +1,249 @@
+//! An interactive playground for lambda terms: parse a term with
+//! `lambdars_core::parser`, reduce it, and print the result. Supports a
+//! handful of meta-commands (`:step`, `:type`, `:normal`, `:applicative`,
+//! `:let`, `:load`) on top of plain expression input, and continues reading
+//! across lines while parentheses are unbalanced or a binder's `\`/`.` is
+//! left dangling, so definitions can span several lines.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use lambdars_core::ast::{EvalError, Expr, Strategy as CoreStrategy};
+use lambdars_core::parser::{identifier_spans, open_parens, parse};
+
+enum Strategy {
+    Normal,
+    Applicative,
+}
+
+impl Strategy {
+    fn to_core(&self) -> CoreStrategy {
+        match self {
+            Strategy::Normal => CoreStrategy::NormalOrder,
+            Strategy::Applicative => CoreStrategy::ApplicativeOrder,
+        }
+    }
+}
+
+struct Repl {
+    defs: HashMap<String, Expr>,
+    strategy: Strategy,
+    step: bool,
+}
+
+impl Repl {
+    fn new() -> Self {
+        Repl {
+            defs: HashMap::new(),
+            strategy: Strategy::Normal,
+            step: false,
+        }
+    }
+
+    fn handle_meta(&mut self, line: &str) -> bool {
+        let line = line.trim();
+        match line {
+            ":step" => {
+                self.step = true;
+                println!("stepping enabled: each beta-reduction will be printed");
+                true
+            }
+            ":normal" => {
+                self.strategy = Strategy::Normal;
+                println!("strategy: normal order");
+                true
+            }
+            ":applicative" => {
+                self.strategy = Strategy::Applicative;
+                println!("strategy: applicative order");
+                true
+            }
+            _ if line.starts_with(":type ") => {
+                self.handle_type(&line[":type ".len()..]);
+                true
+            }
+            _ if line.starts_with(":let ") => {
+                self.handle_let(&line[":let ".len()..]);
+                true
+            }
+            _ if line.starts_with(":load ") => {
+                self.handle_load(line[":load ".len()..].trim());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn handle_type(&mut self, input: &str) {
+        let expanded = self.expand_defs(input);
+        match parse(&expanded) {
+            Ok(expr) => match expr.infer() {
+                Ok(ty) => println!("{} : {}", expr.code(), ty.pretty()),
+                Err(err) => println!("type error: {err}"),
+            },
+            Err(err) => println!("parse error: {err}"),
+        }
+    }
+
+    fn handle_let(&mut self, rest: &str) {
+        let Some((name, body)) = rest.split_once('=') else {
+            println!("usage: :let <name> = <term>");
+            return;
+        };
+        let name = name.trim().to_string();
+        match parse(body.trim()) {
+            Ok(expr) => {
+                self.defs.insert(name.clone(), expr);
+                println!("defined {name}");
+            }
+            Err(err) => println!("parse error: {err}"),
+        }
+    }
+
+    fn handle_load(&mut self, path: &str) {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                for line in contents.lines() {
+                    if !line.trim().is_empty() {
+                        self.eval_line(line);
+                    }
+                }
+            }
+            Err(err) => println!("couldn't load '{path}': {err}"),
+        }
+    }
+
+    /// Replace every occurrence of a bound name with its definition before
+    /// parsing, so `:let`-bound terms behave like macros. Matches whole
+    /// identifier tokens via `identifier_spans`, not substrings, so a def
+    /// named `I` doesn't also rewrite part of an unrelated `Id`. If `input`
+    /// doesn't even tokenize, leaves it untouched; `parse` will report the
+    /// same error.
+    fn expand_defs(&self, input: &str) -> String {
+        let Ok(spans) = identifier_spans(input) else {
+            return input.to_string();
+        };
+        let mut expanded = String::new();
+        let mut cursor = 0;
+        for (range, name) in spans {
+            if let Some(expr) = self.defs.get(&name) {
+                expanded.push_str(&input[cursor..range.start]);
+                expanded.push_str(&format!("({})", expr.code()));
+                cursor = range.end;
+            }
+        }
+        expanded.push_str(&input[cursor..]);
+        expanded
+    }
+
+    fn eval_line(&mut self, input: &str) {
+        let expanded = self.expand_defs(input);
+        let expr = match parse(&expanded) {
+            Ok(expr) => expr,
+            Err(err) => {
+                println!("parse error: {err}");
+                return;
+            }
+        };
+
+        if self.step {
+            let mut current = expr.clone();
+            println!("{}", current.code());
+            while let Some(next) = current.step() {
+                println!("--> {}", next.code());
+                current = next;
+            }
+        } else {
+            match expr.eval_with(self.strategy.to_core()) {
+                Ok(reduced) => println!("{} --> {}", expr.code(), reduced.code()),
+                Err(EvalError::StepLimitExceeded { steps, partial }) => {
+                    println!("{} --> (step limit of {steps} exceeded) {}", expr.code(), partial.code())
+                }
+            }
+        }
+    }
+
+    /// Read one logical input, accumulating continuation lines while
+    /// parentheses are unbalanced or the buffer ends mid-binder (a dangling
+    /// `\`/`λ` awaiting its variables, or a dangling `.` awaiting its body),
+    /// so multi-line definitions can be entered.
+    fn read_input<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+        let mut buffer = String::new();
+        loop {
+            if buffer.is_empty() {
+                print!("lambdars> ");
+            } else {
+                print!("... ");
+            }
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(if buffer.is_empty() { None } else { Some(buffer) });
+            }
+
+            buffer.push_str(&line);
+            if line.trim().starts_with(':') || !needs_continuation(&buffer) {
+                return Ok(Some(buffer));
+            }
+        }
+    }
+}
+
+/// Does `buffer` look like an incomplete term that should keep reading more
+/// lines before parsing: unbalanced parentheses, or trailing off right after
+/// a binder's `\`/`λ` or its `.`?
+fn needs_continuation(buffer: &str) -> bool {
+    open_parens(buffer) > 0 || matches!(buffer.trim_end().chars().last(), Some('\\') | Some('λ') | Some('.'))
+}
+
+fn main() {
+    let mut repl = Repl::new();
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+
+    while let Ok(Some(input)) = Repl::read_input(&mut reader) {
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if repl.handle_meta(input) {
+            continue;
+        }
+        repl.eval_line(input);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_defs_replaces_whole_identifiers_only() {
+        let mut repl = Repl::new();
+        repl.defs.insert("I".to_string(), Expr::abstraction("x", Expr::variable("x")));
+
+        // `Id` contains `I` as a substring but is a distinct identifier and
+        // must be left alone.
+        assert_eq!(repl.expand_defs("Id I"), "Id ((\\x. x))");
+    }
+
+    #[test]
+    fn test_expand_defs_leaves_undefined_names_alone() {
+        let repl = Repl::new();
+        assert_eq!(repl.expand_defs("x y"), "x y");
+    }
+
+    #[test]
+    fn test_needs_continuation_on_unbalanced_parens() {
+        assert!(needs_continuation("(\\x. x"));
+        assert!(!needs_continuation("(\\x. x) y"));
+    }
+
+    #[test]
+    fn test_needs_continuation_on_dangling_binder() {
+        assert!(needs_continuation("\\"));
+        assert!(needs_continuation("L x."));
+        assert!(!needs_continuation("L x. x"));
+    }
+}